@@ -1,26 +1,65 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-struct VersionVector {
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct VersionVector {
     vector:HashMap<String, i64>,
     // TODO(kavi): Add support mutex for thread-safe?
 }
 
-struct Dot (String, i64);
+impl PartialEq for VersionVector {
+    /// Ignores zero-valued entries, so `{A: 1}` equals `{A: 1, B: 0}` -
+    /// both mean "node B has applied nothing", regardless of whether B
+    /// ever got an entry in the underlying map.
+    fn eq(&self, other: &Self) -> bool {
+	let keys = VersionVector::all_keys(&[&self.vector, &other.vector]);
+	keys.iter().all(|k| {
+	    let a = *self.vector.get(k).unwrap_or(&0);
+	    let b = *other.vector.get(k).unwrap_or(&0);
+	    a == b
+	})
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Dot (pub String, pub i64);
+
+/// A right-open range `[start, end)` of a single node's missing dot
+/// counters, as produced by `VersionVector::sub_vv` - the compact,
+/// transfer-friendly form of "the ops one replica has that another lacks".
+/// Counters follow the crate's closed `1..=v` dot convention, so `end` is
+/// always the present node's raw counter plus one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdSpan {
+    pub node_id: String,
+    pub start: i64,
+    pub end: i64,
+}
 
 impl VersionVector {
-    fn new() -> VersionVector {
+    pub fn new() -> VersionVector {
 	VersionVector{
 	    vector: HashMap::new(),
 	}
     }
 
-    fn increment(mut self, node_id:&str) -> Self{
+    pub fn increment(mut self, node_id:&str) -> Self{
 	self.vector.entry(node_id.to_string()).and_modify(|e| *e += 1).or_insert(1);
 	self
     }
 
-    fn descends(&self, w:&VersionVector) -> bool {
+    /// Sets the counter for `node_id` directly, overwriting any existing
+    /// value. Used to build a standalone single-entry `VersionVector` out
+    /// of a `Dot`, e.g. when folding a dot into a CRDT's causal context.
+    pub fn set(mut self, node_id: &str, count: i64) -> Self {
+	self.vector.insert(node_id.to_string(), count);
+	self
+    }
+
+    pub fn descends(&self, w:&VersionVector) -> bool {
 	let keys = VersionVector::all_keys(&[&self.vector, &w.vector]);
 	// All the keys from 'self' should be greater than or equal to same key from 'w'.
 	// So, now if both self and w are same, then it descends(v, v) => true
@@ -40,12 +79,16 @@ impl VersionVector {
 	true
     }
 
-    fn concurrent(&self, w:&VersionVector) -> bool {
+    pub fn concurrent(&self, w:&VersionVector) -> bool {
 	// if neither of them descends from another, then they are concurrent
 	!(self.descends(w) || w.descends(self))
     }
 
-    fn descends_dot(&self, w:&Dot) -> bool {
+    /// `{node: v}` means `node` has applied `v` ops, counted `1..=v`, so a
+    /// dot is considered observed once its counter is `<= v` - in
+    /// particular `descends_dot(&get_dot(node))` is always `true` (same
+    /// convention as `includes_dot`/`extend_to_include` below).
+    pub fn descends_dot(&self, w:&Dot) -> bool {
 	let v = match self.vector.get(&w.0) {
 	    None => 0,
 	    Some(v) => *v
@@ -54,7 +97,7 @@ impl VersionVector {
     }
 
     /// merges the two given vectors via point-wise max.
-    fn merge(&self, w:&VersionVector) -> VersionVector {
+    pub fn merge(&self, w:&VersionVector) -> VersionVector {
 	let slice = vec![&self.vector, &w.vector];
 	let keys = VersionVector::all_keys(&slice[..]);
 	let mut res:HashMap<String, i64> = HashMap::new();
@@ -77,7 +120,7 @@ impl VersionVector {
 	}
     }
     
-    fn get_dot(&self, node_id:&str) -> Dot {
+    pub fn get_dot(&self, node_id:&str) -> Dot {
 	let count = match self.vector.get(node_id) {
 	    None => 0,
 	    Some(v) => *v
@@ -85,6 +128,51 @@ impl VersionVector {
 	Dot(node_id.to_string(), count)
     }
 
+    /// Returns, per node, the span of dots present in `self` but not in
+    /// `other` - the transfer-friendly delta of "ops `self` has that `other`
+    /// lacks", instead of shipping the whole vector. Dots follow the same
+    /// closed `1..=v` convention as `descends_dot`/`includes_dot`, so the
+    /// missing counters for a node are `other_count+1 ..= self_count`,
+    /// encoded as the right-open `IdSpan` `other_count+1 .. self_count+1`.
+    pub fn sub_vv(&self, other: &VersionVector) -> Vec<IdSpan> {
+	let mut spans = Vec::new();
+
+	for (node, &self_count) in self.vector.iter() {
+	    let other_count = match other.vector.get(node) {
+		None => 0,
+		Some(v) => *v,
+	    };
+	    if self_count > other_count {
+		spans.push(IdSpan {
+		    node_id: node.clone(),
+		    start: other_count + 1,
+		    end: self_count + 1,
+		});
+	    }
+	}
+
+	spans
+    }
+
+    /// Extends `self` so that `includes_dot(dot)` holds, without otherwise
+    /// lowering the node's counter.
+    pub fn extend_to_include(mut self, dot: &Dot) -> Self {
+	let current = match self.vector.get(&dot.0) {
+	    None => 0,
+	    Some(v) => *v,
+	};
+	if dot.1 > current {
+	    self.vector.insert(dot.0.clone(), dot.1);
+	}
+	self
+    }
+
+    /// Alias for `descends_dot`, named to read naturally alongside
+    /// `extend_to_include`/`sub_vv`.
+    pub fn includes_dot(&self, dot: &Dot) -> bool {
+	self.descends_dot(dot)
+    }
+
     fn all_keys(clocks: &[&HashMap<String, i64>]) -> HashSet<String> {
 	let mut res = HashSet::new();
 
@@ -97,9 +185,43 @@ impl VersionVector {
     }
 }
 
+impl PartialOrd for VersionVector {
+    /// `v1 < v2` means `v2` descends `v1` (every counter in `v1` is <= the
+    /// matching one in `v2`, with at least one strictly smaller), `v1 ==
+    /// v2` means identical, and `None` means concurrent. A single
+    /// point-wise pass early-exits once it has seen one coordinate greater
+    /// on each side.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+	let keys = VersionVector::all_keys(&[&self.vector, &other.vector]);
+
+	let mut self_greater = false;
+	let mut other_greater = false;
+
+	for k in keys.iter() {
+	    let a = *self.vector.get(k).unwrap_or(&0);
+	    let b = *other.vector.get(k).unwrap_or(&0);
+
+	    if a > b {
+		self_greater = true;
+	    }
+	    if a < b {
+		other_greater = true;
+	    }
+	    if self_greater && other_greater {
+		return None;
+	    }
+	}
+
+	match (self_greater, other_greater) {
+	    (true, false) => Some(Ordering::Greater),
+	    (false, true) => Some(Ordering::Less),
+	    _ => Some(Ordering::Equal),
+	}
+    }
+}
 
 impl Dot {
-    fn descends_vv(&self, w:&VersionVector) -> bool {
+    pub fn descends_vv(&self, w:&VersionVector) -> bool {
 	let v = match w.vector.get(&self.0) {
 	    None => 0,
 	    Some(v) => *v
@@ -109,6 +231,85 @@ impl Dot {
     }
 }
 
+/// Same semantics as `VersionVector`, but backed by `im::HashMap` (a HAMT)
+/// instead of `std::collections::HashMap`. Cloning an `ImVersionVector` is
+/// O(1) structural sharing rather than a full copy of every entry, and
+/// insert/lookup are O(log n) instead of amortized O(1) - a good trade when
+/// a store keeps many long-lived contexts around (e.g. one per client per
+/// key) and clones them on every read.
+///
+/// Same dot convention as `VersionVector`: a vector `{A: 2}` means replica A
+/// has applied 2 ops, counted `1..=2`, so `Dot(A, 2)` is already included
+/// while `Dot(A, 3)` is not yet - see `descends_dot` below. This is the one
+/// convention the whole module uses, including `VersionVector::sub_vv`'s
+/// `IdSpan` output.
+#[derive(Clone)]
+pub struct ImVersionVector {
+    vector: im::HashMap<String, i64>,
+}
+
+impl Default for ImVersionVector {
+    fn default() -> Self {
+	Self::new()
+    }
+}
+
+impl ImVersionVector {
+    pub fn new() -> ImVersionVector {
+	ImVersionVector {
+	    vector: im::HashMap::new(),
+	}
+    }
+
+    pub fn inc(&self, node_id: &str) -> Self {
+	let mut vector = self.vector.clone();
+	vector.entry(node_id.to_string()).and_modify(|e| *e += 1).or_insert(1);
+	ImVersionVector { vector }
+    }
+
+    pub fn descends(&self, w: &ImVersionVector) -> bool {
+	for (k, v2) in w.vector.iter() {
+	    let v1 = match self.vector.get(k) {
+		None => 0,
+		Some(v) => *v,
+	    };
+	    if v1 < *v2 {
+		return false;
+	    }
+	}
+	true
+    }
+
+    pub fn concurrent(&self, w: &ImVersionVector) -> bool {
+	!(self.descends(w) || w.descends(self))
+    }
+
+    /// merges the two given vectors via point-wise max.
+    pub fn merge(&self, w: &ImVersionVector) -> ImVersionVector {
+	let mut res = self.vector.clone();
+	for (k, v2) in w.vector.iter() {
+	    res.entry(k.to_string()).and_modify(|e| *e = std::cmp::max(*e, *v2)).or_insert(*v2);
+	}
+	ImVersionVector { vector: res }
+    }
+
+    pub fn get_dot(&self, node_id: &str) -> Dot {
+	let count = match self.vector.get(node_id) {
+	    None => 0,
+	    Some(v) => *v,
+	};
+	Dot(node_id.to_string(), count)
+    }
+
+    pub fn descends_dot(&self, w: &Dot) -> bool {
+	let v = match self.vector.get(&w.0) {
+	    None => 0,
+	    Some(v) => *v,
+	};
+	v >= w.1
+    }
+}
+
 
 #[test]
 fn test_vv_new() {
@@ -203,5 +404,207 @@ fn test_descends_dot() {
     let dot = Dot("A".to_string(), 1);
     assert!(!dot.descends_vv(&v));
     assert!(v.descends_dot(&dot));
-    
+
+}
+
+#[test]
+fn test_sub_vv_returns_missing_spans() {
+    // self has A@1..=3, B@1; other has A@1. Missing dots: A@2, A@3, B@1.
+    let v1 = VersionVector::new().increment("A").increment("A").increment("A").increment("B");
+    let v2 = VersionVector::new().increment("A");
+
+    let mut spans = v1.sub_vv(&v2);
+    spans.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0], IdSpan { node_id: "A".to_string(), start: 2, end: 4 });
+    assert_eq!(spans[1], IdSpan { node_id: "B".to_string(), start: 1, end: 2 });
+
+    // Every dot the span claims missing must actually be missing from
+    // `other` and present in `self`, per descends_dot/includes_dot.
+    for span in &spans {
+        for counter in span.start..span.end {
+            let dot = Dot(span.node_id.clone(), counter);
+            assert!(v1.includes_dot(&dot));
+            assert!(!v2.includes_dot(&dot));
+        }
+    }
+}
+
+#[test]
+fn test_sub_vv_empty_when_other_is_ahead() {
+    let v1 = VersionVector::new().increment("A");
+    let v2 = VersionVector::new().increment("A").increment("A");
+
+    assert!(v1.sub_vv(&v2).is_empty());
+}
+
+#[test]
+fn test_includes_dot_agrees_with_descends_dot() {
+    let v = VersionVector::new().increment("A").increment("A");
+
+    // {A: 2} counts A@1 and A@2 as applied, but not A@3 - and own last dot
+    // (via get_dot) is always included.
+    assert!(v.includes_dot(&v.get_dot("A")));
+    assert!(v.includes_dot(&Dot("A".to_string(), 1)));
+    assert!(!v.includes_dot(&Dot("A".to_string(), 3)));
+    assert_eq!(v.includes_dot(&v.get_dot("A")), v.descends_dot(&v.get_dot("A")));
+}
+
+#[test]
+fn test_extend_to_include() {
+    let v = VersionVector::new().increment("A");
+    assert!(!v.includes_dot(&Dot("A".to_string(), 3)));
+
+    let v = v.extend_to_include(&Dot("A".to_string(), 3));
+    assert!(v.includes_dot(&Dot("A".to_string(), 3)));
+
+    // extending with an already-included dot must not move the counter backwards.
+    let before = v.get_dot("A").1;
+    let v = v.extend_to_include(&Dot("A".to_string(), 0));
+    assert_eq!(v.get_dot("A").1, before);
+}
+
+#[test]
+fn test_im_vv_inc_is_non_consuming() {
+    let v0 = ImVersionVector::new();
+    let v1 = v0.inc("A");
+    let v2 = v1.inc("A").inc("B");
+
+    assert_eq!(v1.vector.get("A"), Some(&1_i64));
+    assert_eq!(v2.vector.get("A"), Some(&2_i64));
+    assert_eq!(v2.vector.get("B"), Some(&1_i64));
+    // v1 is untouched by the later inc calls on v2 - structural sharing, not mutation.
+    assert_eq!(v1.vector.get("B"), None);
+}
+
+#[test]
+fn test_im_vv_merge() {
+    let v1 = ImVersionVector::new().inc("A").inc("A").inc("B");
+    let v2 = ImVersionVector::new().inc("B").inc("B").inc("A");
+
+    let v3 = v1.merge(&v2);
+
+    assert_eq!(v3.vector.get("A"), Some(&2_i64));
+    assert_eq!(v3.vector.get("B"), Some(&2_i64));
+}
+
+#[test]
+fn test_im_vv_descends_and_concurrent() {
+    let v1 = ImVersionVector::new().inc("A").inc("A").inc("B");
+    let v2 = ImVersionVector::new().inc("A");
+
+    assert!(v1.descends(&v2));
+    assert!(!v2.descends(&v1));
+    assert!(!v1.concurrent(&v2));
+
+    let v3 = ImVersionVector::new().inc("B").inc("B").inc("B");
+    assert!(!v1.descends(&v3));
+    assert!(!v3.descends(&v1));
+    assert!(v1.concurrent(&v3));
+}
+
+#[test]
+fn test_im_vv_get_dot_and_descends_dot() {
+    let v = ImVersionVector::new().inc("A").inc("A").inc("B");
+    let dot = v.get_dot("A");
+
+    assert_eq!("A", dot.0);
+    assert_eq!(2, dot.1);
+
+    // {A: 2} counts A@1 and A@2 as applied (same convention as VersionVector).
+    assert!(v.descends_dot(&Dot("A".to_string(), 2)));
+    assert!(!v.descends_dot(&Dot("A".to_string(), 3)));
+}
+
+#[test]
+fn test_vv_partial_ord_strict_descends() {
+    let v1 = VersionVector::new().increment("A").increment("B");
+    let v2 = VersionVector::new().increment("A").increment("A").increment("B");
+
+    assert!(v1 < v2);
+    assert!(v2 > v1);
+}
+
+#[test]
+fn test_vv_partial_ord_equal() {
+    let v1 = VersionVector::new().increment("A").increment("B");
+    let v2 = VersionVector::new().increment("A").increment("B");
+
+    assert_eq!(v1, v2);
+    assert_eq!(v1.partial_cmp(&v2), Some(Ordering::Equal));
+}
+
+#[test]
+fn test_vv_partial_ord_concurrent_is_none() {
+    let v1 = VersionVector::new().increment("A").increment("A");
+    let v2 = VersionVector::new().increment("B").increment("B");
+
+    assert_eq!(v1.partial_cmp(&v2), None);
+}
+
+#[test]
+fn test_vv_and_dot_serde_roundtrip() {
+    let v = VersionVector::new().increment("A").increment("A").increment("B");
+    let json = serde_json::to_string(&v).unwrap();
+    let back: VersionVector = serde_json::from_str(&json).unwrap();
+    assert_eq!(v, back);
+
+    let dot = v.get_dot("A");
+    let dot_json = serde_json::to_string(&dot).unwrap();
+    let dot_back: Dot = serde_json::from_str(&dot_json).unwrap();
+    assert_eq!(dot, dot_back);
+}
+
+#[test]
+fn test_vv_eq_ignores_zero_valued_entries() {
+    let v1 = VersionVector::new().increment("A");
+    let mut explicit_zero = HashMap::new();
+    explicit_zero.insert("A".to_string(), 1_i64);
+    explicit_zero.insert("B".to_string(), 0_i64);
+    let v2 = VersionVector { vector: explicit_zero };
+
+    assert_eq!(v1, v2);
+}
+
+#[cfg(test)]
+impl quickcheck::Arbitrary for VersionVector {
+    fn arbitrary(g: &mut quickcheck::Gen) -> VersionVector {
+        // Keep the node-id universe small so merges/descends actually
+        // overlap often enough to be an interesting check, and keep counts
+        // small so failing cases shrink to something readable.
+        let nodes = ["A", "B", "C"];
+        let mut vv = VersionVector::new();
+        for &n in nodes.iter() {
+            let count = u8::arbitrary(g) % 4;
+            for _ in 0..count {
+                vv = vv.increment(n);
+            }
+        }
+        vv
+    }
+}
+
+#[cfg(test)]
+quickcheck::quickcheck! {
+    fn prop_merge_is_commutative(a: VersionVector, b: VersionVector) -> bool {
+        a.merge(&b) == b.merge(&a)
+    }
+
+    fn prop_merge_is_associative(a: VersionVector, b: VersionVector, c: VersionVector) -> bool {
+        a.merge(&b).merge(&c) == a.merge(&b.merge(&c))
+    }
+
+    fn prop_merge_is_idempotent(a: VersionVector) -> bool {
+        a.merge(&a) == a
+    }
+
+    fn prop_merge_descends_both_inputs(a: VersionVector, b: VersionVector) -> bool {
+        let merged = a.merge(&b);
+        merged.descends(&a) && merged.descends(&b)
+    }
+
+    fn prop_concurrent_is_symmetric(a: VersionVector, b: VersionVector) -> bool {
+        a.concurrent(&b) == b.concurrent(&a)
+    }
 }
@@ -0,0 +1,217 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// An event id. Parents of an event always have a strictly smaller `Time`
+/// than the event itself, so popping a max-heap of `Time`s largest-first
+/// is guaranteed to visit an event only after all of its descendants.
+pub type Time = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    OnlyA,
+    OnlyB,
+    Shared,
+}
+
+impl Side {
+    fn merge(self, other: Side) -> Side {
+        if self == other {
+            self
+        } else {
+            Side::Shared
+        }
+    }
+}
+
+#[derive(Eq, PartialEq)]
+struct Entry {
+    time: Time,
+    side: Side,
+}
+
+// Ord/PartialOrd only compare `time` - `BinaryHeap` is used purely to
+// always hand us back the largest remaining time, regardless of which
+// side(s) queued it.
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.time.cmp(&other.time)
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A causal event DAG: each event is a `Time` id with a small set of parent
+/// `Time`s it directly depends on. Unlike a `VectorClock`/`VersionVector`,
+/// which only compares whole summaries of a replica's progress, `History`
+/// lets you ask "what changed between these two versions" against the
+/// underlying graph of individual events.
+#[derive(Default)]
+pub struct History {
+    parents: HashMap<Time, Vec<Time>>,
+}
+
+impl History {
+    pub fn new() -> History {
+        History {
+            parents: HashMap::new(),
+        }
+    }
+
+    pub fn add_event(&mut self, time: Time, parents: Vec<Time>) {
+        self.parents.insert(time, parents);
+    }
+
+    fn parents_of(&self, time: Time) -> &[Time] {
+        match self.parents.get(&time) {
+            None => &[],
+            Some(p) => p,
+        }
+    }
+
+    /// Given two frontiers `a` and `b`, returns `(only_a, only_b)`: the
+    /// events in `a`'s causal past but not `b`'s, and vice versa.
+    ///
+    /// Walks the union of both causal pasts once, largest time first via a
+    /// max-heap, tagging each queued time with which frontier(s) reached it.
+    /// Once a time is reached from both sides its tag becomes `Shared` and
+    /// it no longer counts toward either output set. The walk stops as soon
+    /// as every entry still queued is `Shared`, since expanding those can
+    /// only ever produce more `Shared` entries.
+    pub fn diff(&self, a: &[Time], b: &[Time]) -> (HashSet<Time>, HashSet<Time>) {
+        let mut heap = BinaryHeap::new();
+        let mut pending = 0usize;
+
+        for &t in a {
+            heap.push(Entry {
+                time: t,
+                side: Side::OnlyA,
+            });
+            pending += 1;
+        }
+        for &t in b {
+            heap.push(Entry {
+                time: t,
+                side: Side::OnlyB,
+            });
+            pending += 1;
+        }
+
+        let mut only_a = HashSet::new();
+        let mut only_b = HashSet::new();
+
+        while pending > 0 {
+            let top = match heap.pop() {
+                None => break,
+                Some(e) => e,
+            };
+            if top.side != Side::Shared {
+                pending -= 1;
+            }
+
+            // Equal times popped in succession must have their tags
+            // OR-merged (to Shared, if they disagree) before expansion.
+            let mut side = top.side;
+            while let Some(next) = heap.peek() {
+                if next.time != top.time {
+                    break;
+                }
+                let next = heap.pop().unwrap();
+                if next.side != Side::Shared {
+                    pending -= 1;
+                }
+                side = side.merge(next.side);
+            }
+
+            match side {
+                Side::OnlyA => {
+                    only_a.insert(top.time);
+                }
+                Side::OnlyB => {
+                    only_b.insert(top.time);
+                }
+                Side::Shared => {}
+            }
+
+            for &p in self.parents_of(top.time) {
+                heap.push(Entry { time: p, side });
+                if side != Side::Shared {
+                    pending += 1;
+                }
+            }
+        }
+
+        (only_a, only_b)
+    }
+}
+
+#[test]
+fn test_diff_disjoint_histories() {
+    let mut h = History::new();
+    h.add_event(1, vec![]);
+    h.add_event(2, vec![1]);
+    h.add_event(10, vec![]);
+    h.add_event(11, vec![10]);
+
+    let (only_a, only_b) = h.diff(&[2], &[11]);
+    assert_eq!(only_a, [1, 2].into_iter().collect());
+    assert_eq!(only_b, [10, 11].into_iter().collect());
+}
+
+#[test]
+fn test_diff_shared_prefix() {
+    // 1 <- 2 <- 3 (shared), then A continues to 4, B continues to 5.
+    let mut h = History::new();
+    h.add_event(1, vec![]);
+    h.add_event(2, vec![1]);
+    h.add_event(3, vec![2]);
+    h.add_event(4, vec![3]);
+    h.add_event(5, vec![3]);
+
+    let (only_a, only_b) = h.diff(&[4], &[5]);
+    assert_eq!(only_a, [4].into_iter().collect());
+    assert_eq!(only_b, [5].into_iter().collect());
+}
+
+#[test]
+fn test_diff_identical_frontiers() {
+    let mut h = History::new();
+    h.add_event(1, vec![]);
+    h.add_event(2, vec![1]);
+
+    let (only_a, only_b) = h.diff(&[2], &[2]);
+    assert!(only_a.is_empty());
+    assert!(only_b.is_empty());
+}
+
+#[test]
+fn test_diff_one_descends_from_other() {
+    // b's frontier already includes everything in a's causal past.
+    let mut h = History::new();
+    h.add_event(1, vec![]);
+    h.add_event(2, vec![1]);
+    h.add_event(3, vec![2]);
+
+    let (only_a, only_b) = h.diff(&[2], &[3]);
+    assert!(only_a.is_empty());
+    assert_eq!(only_b, [3].into_iter().collect());
+}
+
+#[test]
+fn test_diff_multi_parent_merge_event() {
+    // 4 merges two independent branches (2 and 3), both rooted at 1.
+    let mut h = History::new();
+    h.add_event(1, vec![]);
+    h.add_event(2, vec![1]);
+    h.add_event(3, vec![1]);
+    h.add_event(4, vec![2, 3]);
+
+    let (only_a, only_b) = h.diff(&[4], &[2]);
+    assert_eq!(only_a, [3, 4].into_iter().collect());
+    assert!(only_b.is_empty());
+}
@@ -0,0 +1,9 @@
+pub mod dvv;
+pub mod history;
+pub mod orswot;
+pub mod vclock;
+
+pub use dvv::{Dot, IdSpan, ImVersionVector, VersionVector};
+pub use history::{History, Time};
+pub use orswot::Orswot;
+pub use vclock::{Lamport, Local, Timestamp, VectorClock};
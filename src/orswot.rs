@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::dvv::Dot;
+use crate::dvv::VersionVector;
+
+/// An add-wins observed-remove set (ORSWOT), generalizing the sibling-merge
+/// logic demonstrated by `KVStore` in `examples/kv.rs` into a reusable CRDT.
+///
+/// Every member is tagged with the `Dot` of the actor that added it. `merge`
+/// keeps concurrent additions/removals and drops any dot the other replica
+/// has already causally observed and no longer holds (i.e. has removed),
+/// converging to the same set everywhere.
+#[derive(Default)]
+pub struct Orswot<T: Eq + Hash + Clone> {
+    members: HashMap<T, HashSet<Dot>>,
+    vv: VersionVector,
+}
+
+impl<T: Eq + Hash + Clone> Orswot<T> {
+    pub fn new() -> Orswot<T> {
+        Orswot {
+            members: HashMap::new(),
+            vv: VersionVector::new(),
+        }
+    }
+
+    /// Records `elem` against the actor's latest `dot`, and folds that dot
+    /// into this replica's causal context.
+    pub fn add(&mut self, elem: T, dot: Dot) {
+        self.vv = self.vv.clone().merge(&VersionVector::new().set(&dot.0, dot.1));
+        self.members.entry(elem).or_default().insert(dot);
+    }
+
+    /// Drops every dot for `elem` that `context` has already observed,
+    /// leaving behind only dots added concurrently with this remove.
+    pub fn remove(&mut self, elem: &T, context: &VersionVector) {
+        if let Some(dots) = self.members.get_mut(elem) {
+            dots.retain(|d| !context.descends_dot(d));
+            if dots.is_empty() {
+                self.members.remove(elem);
+            }
+        }
+        self.vv = self.vv.clone().merge(context);
+    }
+
+    /// Merges `other` into a new `Orswot`, keeping a member-dot iff the
+    /// other replica's member set already has it, or it is concurrent with
+    /// (not dominated by) the other replica's version vector.
+    pub fn merge(&self, other: &Orswot<T>) -> Orswot<T> {
+        let mut members: HashMap<T, HashSet<Dot>> = HashMap::new();
+
+        let mut keep_survivors = |src: &HashMap<T, HashSet<Dot>>,
+                                   other_members: &HashMap<T, HashSet<Dot>>,
+                                   other_vv: &VersionVector| {
+            for (elem, dots) in src.iter() {
+                for dot in dots.iter() {
+                    let seen_by_other = other_members.get(elem).is_some_and(|d| d.contains(dot));
+                    let concurrent = !other_vv.descends_dot(dot);
+                    if seen_by_other || concurrent {
+                        members.entry(elem.clone()).or_default().insert(dot.clone());
+                    }
+                }
+            }
+        };
+
+        keep_survivors(&self.members, &other.members, &other.vv);
+        keep_survivors(&other.members, &self.members, &self.vv);
+
+        Orswot {
+            members,
+            vv: self.vv.clone().merge(&other.vv),
+        }
+    }
+
+    pub fn read(&self) -> HashSet<T> {
+        self.members.keys().cloned().collect()
+    }
+}
+
+#[test]
+fn test_merge_unions_disjoint_adds() {
+    let mut a = Orswot::new();
+    let mut b = Orswot::new();
+
+    a.add("x", Dot("A".to_string(), 1));
+    b.add("y", Dot("B".to_string(), 1));
+
+    let merged = a.merge(&b);
+    assert_eq!(merged.read(), ["x", "y"].iter().cloned().collect());
+}
+
+#[test]
+fn test_remove_drops_observed_dot() {
+    let mut a = Orswot::new();
+    a.add("x", Dot("A".to_string(), 1));
+
+    let context = a.merge(&Orswot::new()).vv.clone();
+    a.remove(&"x", &context);
+
+    assert!(a.read().is_empty());
+}
+
+#[test]
+fn test_concurrent_add_and_remove_is_add_wins() {
+    let mut a = Orswot::new();
+    a.add("x", Dot("A".to_string(), 1));
+
+    // B removes "x" using an empty context - the context has observed none
+    // of A's dots, so nothing is dropped and B's vv is left unchanged.
+    let mut b = a.merge(&Orswot::new());
+    let remove_context = VersionVector::new();
+    b.remove(&"x", &remove_context);
+
+    // A concurrently re-adds "x" with a fresh dot unseen by B.
+    let mut a2 = Orswot::new();
+    a2.add("x", Dot("A".to_string(), 2));
+
+    let merged = b.merge(&a2);
+    assert_eq!(merged.read(), ["x"].iter().cloned().collect());
+}
+
+#[test]
+fn test_merge_is_commutative() {
+    let mut a = Orswot::new();
+    a.add("x", Dot("A".to_string(), 1));
+
+    let mut b = Orswot::new();
+    b.add("y", Dot("B".to_string(), 1));
+
+    assert_eq!(a.merge(&b).read(), b.merge(&a).read());
+}
@@ -1,12 +1,32 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-#[derive(Default)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct VectorClock {
     vector: HashMap<String, i64>,
     // TODO(kavi): Add support mutex for thread-safe?
 }
 
+impl PartialEq for VectorClock {
+    /// Ignores zero-valued entries, so `{A: 1}` equals `{A: 1, B: 0}` -
+    /// both mean "node B has applied nothing", regardless of whether B
+    /// ever got an entry in the underlying map (e.g. after a `serde`
+    /// round-trip of an explicit zero). Keeps this consistent with
+    /// `PartialOrd` below, which already treats a missing key and an
+    /// explicit zero identically.
+    fn eq(&self, other: &Self) -> bool {
+	let keys = VectorClock::all_keys(&[&self.vector, &other.vector]);
+	keys.iter().all(|k| {
+	    let a = *self.vector.get(k).unwrap_or(&0);
+	    let b = *other.vector.get(k).unwrap_or(&0);
+	    a == b
+	})
+    }
+}
+
 impl VectorClock {
     pub fn new() -> VectorClock {
         VectorClock {
@@ -90,6 +110,160 @@ impl VectorClock {
     }
 }
 
+impl PartialOrd for VectorClock {
+    /// `v1 < v2` means `v1` strictly happened-before `v2`, `v1 == v2` means
+    /// identical, and `None` means the two are concurrent - a single
+    /// point-wise pass that early-exits the moment it has seen one
+    /// coordinate greater on each side (at that point neither can dominate
+    /// the other, so no more keys need checking).
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let keys = VectorClock::all_keys(&[&self.vector, &other.vector]);
+
+        let mut self_greater = false;
+        let mut other_greater = false;
+
+        for k in keys.iter() {
+            let a = *self.vector.get(k).unwrap_or(&0);
+            let b = *other.vector.get(k).unwrap_or(&0);
+
+            if a > b {
+                self_greater = true;
+            }
+            if a < b {
+                other_greater = true;
+            }
+            if self_greater && other_greater {
+                return None;
+            }
+        }
+
+        match (self_greater, other_greater) {
+            (true, false) => Some(Ordering::Greater),
+            (false, true) => Some(Ordering::Less),
+            _ => Some(Ordering::Equal),
+        }
+    }
+}
+
+/// A totally-ordered timestamp produced by `Lamport::tick`.
+///
+/// Ordering compares `count` first, falling back to `replica_id` to break
+/// ties between events ticked by different replicas at the same count.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp {
+    count: i64,
+    replica_id: String,
+}
+
+/// A Lamport scalar clock: a single replica-local counter that yields a
+/// compact, totally-ordered `Timestamp` for event ordering, in contrast to
+/// `VectorClock` which tracks every replica's progress individually.
+///
+/// Conceptually this is the same "replica owns a counter" shape as a
+/// `Dot` (a `(replica, counter)` pair in `dvv::VersionVector`), but collapsed
+/// to a single scalar rather than a whole version vector.
+pub struct Lamport {
+    count: i64,
+    replica_id: String,
+}
+
+impl Lamport {
+    pub fn new(replica_id: &str) -> Lamport {
+        Lamport {
+            count: 0,
+            replica_id: replica_id.to_string(),
+        }
+    }
+
+    /// Advances the local count and returns a new totally-ordered timestamp.
+    pub fn tick(&mut self) -> Timestamp {
+        self.count += 1;
+        Timestamp {
+            count: self.count,
+            replica_id: self.replica_id.clone(),
+        }
+    }
+
+    /// Fast-forwards the local count so that the next `tick` sorts after
+    /// `other`, ensuring received events are ordered after their causes.
+    pub fn observe(&mut self, other: &Timestamp) {
+        self.count = std::cmp::max(self.count, other.count);
+    }
+}
+
+/// A simple per-replica operation counter, with no notion of a totally
+/// ordered timestamp. Useful when all you need is "how many ops has this
+/// replica applied", e.g. as the count half of a `Dot`.
+pub struct Local {
+    count: i64,
+    replica_id: String,
+}
+
+impl Local {
+    pub fn new(replica_id: &str) -> Local {
+        Local {
+            count: 0,
+            replica_id: replica_id.to_string(),
+        }
+    }
+
+    /// Increments and returns the new count.
+    pub fn tick(&mut self) -> i64 {
+        self.count += 1;
+        self.count
+    }
+
+    /// Fast-forwards the local count to at least `other`.
+    pub fn observe(&mut self, other: i64) {
+        self.count = std::cmp::max(self.count, other);
+    }
+
+    pub fn count(&self) -> i64 {
+        self.count
+    }
+
+    pub fn replica_id(&self) -> &str {
+        &self.replica_id
+    }
+}
+
+#[test]
+fn test_lamport_observe_orders_after_cause() {
+    let mut a = Lamport::new("A");
+    let mut b = Lamport::new("B");
+
+    let ta1 = a.tick(); // A@1
+    b.observe(&ta1);
+    let tb1 = b.tick(); // B@2, observed A@1 first
+
+    assert!(ta1 < tb1);
+}
+
+#[test]
+fn test_lamport_tiebreak_by_replica_id() {
+    let mut a = Lamport::new("A");
+    let mut b = Lamport::new("B");
+
+    let ta1 = a.tick();
+    let tb1 = b.tick();
+
+    assert!(ta1 < tb1);
+}
+
+#[test]
+fn test_local_observe_fast_forwards() {
+    let mut l = Local::new("A");
+    l.tick();
+    l.tick();
+    assert_eq!(l.count(), 2);
+
+    l.observe(5);
+    assert_eq!(l.count(), 5);
+
+    l.observe(1);
+    assert_eq!(l.count(), 5); // observe never moves backwards
+}
+
 #[test]
 fn test_vv_new() {
     let mut vv = VectorClock::new();
@@ -217,3 +391,52 @@ fn test_vv_concurrent() {
     assert!(v1.concurrent(&v2));
     assert!(v2.concurrent(&v1));
 }
+
+#[test]
+fn test_partial_ord_strict_happened_before() {
+    let v1 = VectorClock::new().inc("A").inc("B");
+    let v2 = VectorClock::new().inc("A").inc("A").inc("B");
+
+    assert!(v1 < v2);
+    assert!(v2 > v1);
+}
+
+#[test]
+fn test_partial_ord_equal() {
+    let v1 = VectorClock::new().inc("A").inc("B");
+    let v2 = VectorClock::new().inc("A").inc("B");
+
+    assert_eq!(v1, v2);
+    assert_eq!(v1.partial_cmp(&v2), Some(Ordering::Equal));
+}
+
+#[test]
+fn test_partial_ord_concurrent_is_none() {
+    let v1 = VectorClock::new().inc("A").inc("A");
+    let v2 = VectorClock::new().inc("B").inc("B");
+
+    assert_eq!(v1.partial_cmp(&v2), None);
+    assert_ne!(v1, v2);
+}
+
+#[test]
+fn test_serde_roundtrip() {
+    let v = VectorClock::new().inc("A").inc("A").inc("B");
+
+    let json = serde_json::to_string(&v).unwrap();
+    let back: VectorClock = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(v, back);
+}
+
+#[test]
+fn test_eq_ignores_zero_valued_entries() {
+    let v1 = VectorClock::new().inc("A");
+    let mut explicit_zero = HashMap::new();
+    explicit_zero.insert("A".to_string(), 1_i64);
+    explicit_zero.insert("B".to_string(), 0_i64);
+    let v2 = VectorClock { vector: explicit_zero };
+
+    assert_eq!(v1, v2);
+    assert_eq!(v1.partial_cmp(&v2), Some(Ordering::Equal));
+}